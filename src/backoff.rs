@@ -0,0 +1,37 @@
+//! Reconnect backoff policy for the combined-stream connection.
+use rand::Rng;
+use std::time::Duration;
+
+/// Exponential backoff with jitter for reconnect attempts, resetting once a
+/// connection has stayed healthy for long enough that the outage is over.
+#[derive(Clone, Copy, Debug)]
+pub struct BackoffPolicy {
+    /// Delay after the first consecutive failure.
+    pub base: Duration,
+    /// Upper bound on the delay, before jitter is added.
+    pub max: Duration,
+    /// How long a connection must stay up before the failure count resets.
+    pub healthy_after: Duration,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_millis(500),
+            max: Duration::from_secs(30),
+            healthy_after: Duration::from_secs(60),
+        }
+    }
+}
+
+impl BackoffPolicy {
+    /// The delay to sleep before the next reconnect attempt, given the
+    /// number of consecutive failures seen so far (0 before any failure).
+    pub fn delay_for(&self, consecutive_failures: u32) -> Duration {
+        let shift = consecutive_failures.saturating_sub(1).min(16);
+        let capped = self.base.saturating_mul(1u32 << shift).min(self.max);
+        let jitter_cap_ms = (capped.as_millis() as u64) / 4 + 1;
+        let jitter = Duration::from_millis(rand::thread_rng().gen_range(0..=jitter_cap_ms));
+        capped + jitter
+    }
+}
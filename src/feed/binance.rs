@@ -0,0 +1,272 @@
+//! The Binance combined-stream feed. Preserves the behavior `OrderBooks`
+//! had before the `ExchangeFeed` abstraction was introduced: a depth event
+//! is valid iff its `first_update_id` is one more than the last applied
+//! `final_update_id`.
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_this_or_that::as_f64;
+
+use super::{ExchangeFeed, NormalizedEvent, NormalizedSnapshot};
+
+const COMBINED_STREAM_URL: &str = "wss://stream.binance.com:9443/stream";
+
+/// Binance's depth stream update speed: the default is throttled to once a
+/// second, `Ms100` opts into the faster (but heavier) 100ms cadence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum UpdateSpeed {
+    #[default]
+    Ms1000,
+    Ms100,
+}
+
+impl UpdateSpeed {
+    fn stream_suffix(self) -> &'static str {
+        match self {
+            UpdateSpeed::Ms1000 => "",
+            UpdateSpeed::Ms100 => "@100ms",
+        }
+    }
+}
+
+/// Configuration for how much depth to track and how fast to receive it.
+///
+/// By default (`levels: None`) this preserves prior behavior: the full
+/// diff stream at its default once-a-second speed (`@depth`) and a REST
+/// snapshot requested without `&limit=`, retaining every price level
+/// forever. Setting `levels` switches to Binance's partial-book-depth
+/// stream (`@depth{levels}`, or `@depth{levels}@100ms` with
+/// `UpdateSpeed::Ms100`) and passes
+/// `&limit={levels}` to the snapshot endpoint, and `OrderBooks` prunes each
+/// book down to the top `levels` price levels per side after every update.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DepthConfig {
+    levels: Option<u32>,
+    update_speed: UpdateSpeed,
+}
+
+impl DepthConfig {
+    /// Subscribe to Binance's partial-book-depth stream, tracking the top
+    /// `levels` price levels per side. Binance only accepts 5, 10, or 20
+    /// levels for this stream; any other value is rejected here rather than
+    /// silently subscribing to a stream name Binance will reject.
+    pub fn partial(levels: u32, update_speed: UpdateSpeed) -> Result<Self> {
+        if !matches!(levels, 5 | 10 | 20) {
+            return Err(anyhow!(
+                "invalid partial-depth levels {levels}, Binance only accepts 5, 10, or 20"
+            ));
+        }
+
+        Ok(Self {
+            levels: Some(levels),
+            update_speed,
+        })
+    }
+}
+
+/// Feeds order books from Binance's combined-stream endpoint.
+#[derive(Clone, Debug, Default)]
+pub struct Binance {
+    depth: DepthConfig,
+}
+
+impl Binance {
+    pub fn new(depth: DepthConfig) -> Self {
+        Self { depth }
+    }
+}
+
+#[async_trait]
+impl ExchangeFeed for Binance {
+    async fn snapshot(&self, symbol: &str) -> Result<NormalizedSnapshot> {
+        let mut url = format!(
+            "https://api.binance.com/api/v3/depth?symbol={}",
+            symbol.to_uppercase()
+        );
+        if let Some(levels) = self.depth.levels {
+            url += &format!("&limit={levels}");
+        }
+
+        let snapshot = reqwest::get(url).await?.json::<Snapshot>().await?;
+        Ok(NormalizedSnapshot {
+            bids: snapshot.bids.iter().map(Item::as_tuple).collect(),
+            asks: snapshot.asks.iter().map(Item::as_tuple).collect(),
+        })
+    }
+
+    fn stream_url(&self) -> &str {
+        COMBINED_STREAM_URL
+    }
+
+    fn stream_param(&self, symbol: &str) -> String {
+        let symbol = symbol.to_lowercase();
+        let suffix = self.depth.update_speed.stream_suffix();
+        match self.depth.levels {
+            Some(levels) => format!("{symbol}@depth{levels}{suffix}"),
+            None => format!("{symbol}@depth{suffix}"),
+        }
+    }
+
+    fn parse_event(&self, data: &[u8]) -> Result<NormalizedEvent> {
+        let envelope: StreamEnvelope = serde_json::from_slice(data)?;
+
+        if self.depth.levels.is_some() {
+            // Partial-book-depth payloads are an absolute top-N snapshot
+            // (`{lastUpdateId, bids, asks}`), not a diff: there's no `s`
+            // field to take the symbol from, so it comes from the stream
+            // name instead, e.g. "btcusdt@depth20@100ms".
+            let symbol = envelope
+                .stream
+                .split("@depth")
+                .next()
+                .ok_or_else(|| anyhow!("malformed stream name {}", envelope.stream))?
+                .to_lowercase();
+            let event: PartialDepthEvent = serde_json::from_value(envelope.data)?;
+            return Ok(NormalizedEvent {
+                symbol,
+                first_update_id: event.last_update_id as u64,
+                final_update_id: event.last_update_id as u64,
+                bids: event.bids.iter().map(Item::as_tuple).collect(),
+                asks: event.asks.iter().map(Item::as_tuple).collect(),
+                is_snapshot: true,
+            });
+        }
+
+        let event: StreamEvent = serde_json::from_value(envelope.data)?;
+        Ok(NormalizedEvent {
+            symbol: event.symbol.to_lowercase(),
+            first_update_id: event.first_update_id as u64,
+            final_update_id: event.final_update_id as u64,
+            bids: event.bids.iter().map(Item::as_tuple).collect(),
+            asks: event.asks.iter().map(Item::as_tuple).collect(),
+            is_snapshot: false,
+        })
+    }
+
+    fn validate_sequence(&self, prev: Option<u64>, event: &NormalizedEvent) -> bool {
+        match prev {
+            None => true,
+            Some(final_update_id) => event.first_update_id == final_update_id + 1,
+        }
+    }
+
+    fn max_levels(&self) -> Option<usize> {
+        self.depth.levels.map(|levels| levels as usize)
+    }
+}
+
+/// Envelope wrapping every message on the combined-stream endpoint, e.g.
+/// `{"stream":"btcusdt@depth","data":{...}}`. `data`'s shape depends on
+/// which stream it came from (diff-depth vs. partial-book-depth), so it's
+/// deferred here and parsed as either `StreamEvent` or `PartialDepthEvent`
+/// once `parse_event` knows which one to expect.
+#[derive(Deserialize, Debug)]
+struct StreamEnvelope {
+    stream: String,
+    data: serde_json::Value,
+}
+
+#[derive(Deserialize, Debug)]
+struct StreamEvent {
+    #[serde(alias = "s")]
+    symbol: String,
+    #[serde(alias = "U")]
+    first_update_id: usize,
+    #[serde(alias = "u")]
+    final_update_id: usize,
+    #[serde(alias = "b")]
+    bids: Vec<Item>,
+    #[serde(alias = "a")]
+    asks: Vec<Item>,
+}
+
+/// A partial-book-depth stream frame: an absolute top-N snapshot with no
+/// symbol or sequence-gap fields at all, just `{lastUpdateId, bids, asks}`.
+#[derive(Deserialize, Debug)]
+struct PartialDepthEvent {
+    #[serde(rename = "lastUpdateId")]
+    last_update_id: usize,
+    bids: Vec<Item>,
+    asks: Vec<Item>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Item {
+    #[serde(deserialize_with = "as_f64")]
+    price: f64,
+    #[serde(deserialize_with = "as_f64")]
+    quantity: f64,
+}
+
+impl Item {
+    fn as_tuple(&self) -> (f64, f64) {
+        (self.price, self.quantity)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Snapshot {
+    bids: Vec<Item>,
+    asks: Vec<Item>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_event_diff_depth() {
+        let binance = Binance::default();
+        let data = br#"{
+            "stream": "btcusdt@depth",
+            "data": {
+                "e": "depthUpdate",
+                "E": 123456789,
+                "s": "BTCUSDT",
+                "U": 157,
+                "u": 160,
+                "b": [["0.0024", "10"]],
+                "a": [["0.0026", "100"]]
+            }
+        }"#;
+
+        let event = binance.parse_event(data).unwrap();
+        assert_eq!(event.symbol, "btcusdt");
+        assert_eq!(event.first_update_id, 157);
+        assert_eq!(event.final_update_id, 160);
+        assert_eq!(event.bids, vec![(0.0024, 10.0)]);
+        assert_eq!(event.asks, vec![(0.0026, 100.0)]);
+        assert!(!event.is_snapshot);
+    }
+
+    #[test]
+    fn parse_event_partial_depth() {
+        let binance = Binance::new(DepthConfig::partial(20, UpdateSpeed::Ms100).unwrap());
+        let data = br#"{
+            "stream": "btcusdt@depth20@100ms",
+            "data": {
+                "lastUpdateId": 160,
+                "bids": [["0.0024", "10"]],
+                "asks": [["0.0026", "100"]]
+            }
+        }"#;
+
+        let event = binance.parse_event(data).unwrap();
+        assert_eq!(event.symbol, "btcusdt");
+        assert_eq!(event.first_update_id, 160);
+        assert_eq!(event.final_update_id, 160);
+        assert_eq!(event.bids, vec![(0.0024, 10.0)]);
+        assert_eq!(event.asks, vec![(0.0026, 100.0)]);
+        assert!(event.is_snapshot);
+    }
+
+    #[test]
+    fn partial_depth_payload_has_no_sequence_fields() {
+        // A partial-depth payload parsed as a diff-stream event (the bug
+        // this series shipped once already) must fail to deserialize rather
+        // than silently succeed with bogus data, since it has no s/U/u keys.
+        let data = br#"{"lastUpdateId": 160, "bids": [], "asks": []}"#;
+        let result: Result<StreamEvent, _> = serde_json::from_slice(data);
+        assert!(result.is_err());
+    }
+}
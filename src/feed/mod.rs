@@ -0,0 +1,63 @@
+//! Exchange feed abstraction.
+//!
+//! Everything venue-specific — REST/websocket URLs, wire formats, and the
+//! rule for deciding whether one depth update directly follows another —
+//! lives behind `ExchangeFeed`. `OrderBooks` drives any implementation the
+//! same way, so adding a venue whose diffs use different sequencing
+//! semantics doesn't touch the book core.
+mod binance;
+
+pub use binance::{Binance, DepthConfig, UpdateSpeed};
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// A normalized REST snapshot, used to seed a book before diffs are applied.
+#[derive(Debug)]
+pub struct NormalizedSnapshot {
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+/// A normalized incremental depth update.
+#[derive(Debug)]
+pub struct NormalizedEvent {
+    /// Lowercased symbol, used to route the event to its book.
+    pub symbol: String,
+    pub first_update_id: u64,
+    pub final_update_id: u64,
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+    /// Whether `bids`/`asks` are an absolute top-N snapshot (e.g. a
+    /// partial-depth stream frame) rather than a diff, and should replace a
+    /// book's levels outright instead of being merged in.
+    pub is_snapshot: bool,
+}
+
+/// A source of order book snapshots and incremental depth updates.
+#[async_trait]
+pub trait ExchangeFeed: Send + Sync + 'static {
+    /// Fetch a REST snapshot to seed `symbol`'s book.
+    async fn snapshot(&self, symbol: &str) -> Result<NormalizedSnapshot>;
+
+    /// The combined-stream websocket URL to connect to.
+    fn stream_url(&self) -> &str;
+
+    /// The stream parameter to SUBSCRIBE/UNSUBSCRIBE for `symbol`, e.g.
+    /// `btcusdt@depth`.
+    fn stream_param(&self, symbol: &str) -> String;
+
+    /// Parse a raw websocket frame into a normalized event.
+    fn parse_event(&self, data: &[u8]) -> Result<NormalizedEvent>;
+
+    /// Whether `event` is the direct successor of the last applied update
+    /// (`prev`'s final_update_id, `None` if the book hasn't been seeded yet).
+    fn validate_sequence(&self, prev: Option<u64>, event: &NormalizedEvent) -> bool;
+
+    /// Cap on retained price levels per side, if books fed from this
+    /// `ExchangeFeed` should be pruned down to it after every update (e.g.
+    /// to match a partial-depth stream). `None` retains every level forever.
+    fn max_levels(&self) -> Option<usize> {
+        None
+    }
+}
@@ -1,39 +1,362 @@
 //! The top level order books module
 //! Provides the OrderBooks struct which if created in an async runtime
 //! will update itself forever
-mod models;
+mod backoff;
+mod feed;
+
+pub use backoff::BackoffPolicy;
+pub use feed::{Binance, DepthConfig, ExchangeFeed, NormalizedEvent, NormalizedSnapshot, UpdateSpeed};
 
 use anyhow::{anyhow, Result};
 use float_ord::FloatOrd;
-use futures::StreamExt;
+use futures::{SinkExt, StreamExt};
 use log::{debug, info, warn};
+use serde::Serialize;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::{broadcast, mpsc};
 use tokio::time::timeout;
 use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
 
-use models::{Snapshot, StreamEvent};
+/// Capacity of each book's update-notification channel: enough deltas to
+/// absorb a brief stall in a downstream client before it starts lagging.
+const UPDATE_CHANNEL_CAPACITY: usize = 1024;
 
-/// Top Level Struct containing order books for a set of securities
-pub struct OrderBooks {
-    pub books: HashMap<String, OrderBook>,
+/// Top Level Struct containing order books for a set of securities.
+///
+/// All books are multiplexed over a single websocket connection to the
+/// feed's combined-stream endpoint: incoming frames are parsed into
+/// `NormalizedEvent`s and routed by symbol to the matching `OrderBook`.
+/// `F` is the `ExchangeFeed` driving the connection, defaulting to
+/// `Binance`; use `with_feed` to plug in another venue.
+pub struct OrderBooks<F: ExchangeFeed = Binance> {
+    books: Arc<Mutex<HashMap<String, OrderBook>>>,
+    feed: Arc<F>,
+    control: mpsc::UnboundedSender<Message>,
+    next_request_id: AtomicU64,
+    /// Tracks the last applied final_update_id per symbol for the live
+    /// connection; shared with the connection task so `unsubscribe` can
+    /// drop a symbol's stale entry immediately instead of waiting for it
+    /// to self-heal via the missed-updates reseed path.
+    last_update_id: Arc<Mutex<HashMap<String, u64>>>,
 }
 
-impl OrderBooks {
+impl OrderBooks<Binance> {
     pub fn new(names: &[String]) -> Self {
-        let mut books = HashMap::new();
-        for name in names {
-            let book = OrderBook::new(name.to_string());
-            // For each book spawn a task to keep it updated forever
-            tokio::spawn(book.clone().update_forever());
-            books.insert(name.to_string(), book);
+        Self::with_feed(Binance::default(), names)
+    }
+}
+
+impl<F: ExchangeFeed> OrderBooks<F> {
+    pub fn with_feed(feed: F, names: &[String]) -> Self {
+        Self::with_config(feed, names, BackoffPolicy::default())
+    }
+
+    /// Like `with_feed`, but with a tuned reconnect `BackoffPolicy` instead
+    /// of the default.
+    pub fn with_config(feed: F, names: &[String], backoff: BackoffPolicy) -> Self {
+        let max_levels = feed.max_levels();
+        let books: HashMap<String, OrderBook> = names
+            .iter()
+            .map(|name| {
+                (
+                    name.to_lowercase(),
+                    OrderBook::with_max_levels(name.to_string(), max_levels),
+                )
+            })
+            .collect();
+        let books = Arc::new(Mutex::new(books));
+        let feed = Arc::new(feed);
+        let last_update_id = Arc::new(Mutex::new(HashMap::new()));
+
+        let (control_tx, control_rx) = mpsc::unbounded_channel();
+
+        // Spawn the single task that keeps every book updated forever
+        tokio::spawn(update_forever(
+            books.clone(),
+            feed.clone(),
+            control_rx,
+            backoff,
+            last_update_id.clone(),
+        ));
+
+        Self {
+            books,
+            feed,
+            control: control_tx,
+            next_request_id: AtomicU64::new(1),
+            last_update_id,
+        }
+    }
+
+    /// Look up a tracked book by symbol, e.g. for display.
+    pub fn get(&self, name: &str) -> Option<OrderBook> {
+        self.books.lock().unwrap().get(&name.to_lowercase()).cloned()
+    }
+
+    /// Start tracking `symbol` at runtime: insert its book and send a
+    /// SUBSCRIBE request on the live connection, so it starts receiving
+    /// updates without a reconnect.
+    pub fn subscribe(&self, symbol: &str) {
+        let max_levels = self.feed.max_levels();
+        self.books
+            .lock()
+            .unwrap()
+            .entry(symbol.to_lowercase())
+            .or_insert_with(|| OrderBook::with_max_levels(symbol.to_string(), max_levels));
+        self.send_control("SUBSCRIBE", vec![self.feed.stream_param(symbol)]);
+    }
+
+    /// Stop tracking `symbol` at runtime: drop its book and send an
+    /// UNSUBSCRIBE request on the live connection.
+    pub fn unsubscribe(&self, symbol: &str) {
+        let key = symbol.to_lowercase();
+        self.books.lock().unwrap().remove(&key);
+        self.last_update_id.lock().unwrap().remove(&key);
+        self.send_control("UNSUBSCRIBE", vec![self.feed.stream_param(symbol)]);
+    }
+
+    /// Bind `addr` and re-broadcast maintained books to any connecting
+    /// client. A client's first message selects a book by symbol name; it
+    /// then receives an initial JSON snapshot followed by incremental
+    /// deltas as events are applied, until it disconnects.
+    pub async fn serve(&self, addr: impl ToSocketAddrs) -> Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let books = self.books.clone();
+            tokio::spawn(async move {
+                if let Err(e) = serve_client(stream, books).await {
+                    warn!("Client {} disconnected with error: {}", peer, e);
+                }
+            });
+        }
+    }
+
+    fn send_control(&self, method: &str, streams: Vec<String>) {
+        let id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        let request = serde_json::json!({ "method": method, "params": streams, "id": id });
+        if self
+            .control
+            .send(Message::Text(request.to_string()))
+            .is_err()
+        {
+            warn!("Dropped {method} request for {streams:?}, connection task has exited");
+        }
+    }
+}
+
+/// Keep the combined-stream connection updated forever, graciously handling
+/// errors by reconnecting and resubscribing to every currently-tracked book.
+/// Reconnects back off exponentially (with jitter) on consecutive failures,
+/// so an outage doesn't turn into a hammering retry loop; the failure count
+/// resets once a connection has stayed up for `backoff.healthy_after`.
+async fn update_forever<F: ExchangeFeed>(
+    books: Arc<Mutex<HashMap<String, OrderBook>>>,
+    feed: Arc<F>,
+    mut control_rx: mpsc::UnboundedReceiver<Message>,
+    backoff: BackoffPolicy,
+    last_update_id: Arc<Mutex<HashMap<String, u64>>>,
+) {
+    let mut consecutive_failures: u32 = 0;
+    loop {
+        let connected_at = Instant::now();
+        match update_until_error(&books, feed.as_ref(), &mut control_rx, &last_update_id).await {
+            Ok(_) => info!("Combined stream closed"),
+            Err(e) => warn!("Combined stream failed with error: {}", e),
+        }
+
+        if connected_at.elapsed() >= backoff.healthy_after {
+            consecutive_failures = 0;
+        } else {
+            consecutive_failures += 1;
         }
-        Self { books }
+
+        let delay = backoff.delay_for(consecutive_failures);
+        debug!(
+            "Reconnecting in {:?} ({} consecutive failures)",
+            delay, consecutive_failures
+        );
+        tokio::time::sleep(delay).await;
     }
 }
 
+/// Connect to the feed's combined-stream endpoint, subscribe to every
+/// currently tracked book, and keep demultiplexing events to the right
+/// `OrderBook` until the connection closes or an error is hit.
+async fn update_until_error<F: ExchangeFeed>(
+    books: &Arc<Mutex<HashMap<String, OrderBook>>>,
+    feed: &F,
+    control_rx: &mut mpsc::UnboundedReceiver<Message>,
+    last_update_id: &Arc<Mutex<HashMap<String, u64>>>,
+) -> Result<()> {
+    let (ws_stream, _) = connect_async(feed.stream_url()).await?;
+    let (mut write, mut read) = ws_stream.split();
+
+    let streams: Vec<String> = books
+        .lock()
+        .unwrap()
+        .keys()
+        .map(|name| feed.stream_param(name))
+        .collect();
+    if !streams.is_empty() {
+        let request = serde_json::json!({ "method": "SUBSCRIBE", "params": streams, "id": 1 });
+        write.send(Message::Text(request.to_string())).await?;
+    }
+
+    // A new connection may have missed updates during any gap before it was
+    // established, so every currently tracked symbol must be reseeded from a
+    // fresh snapshot: discard state left over from the previous connection.
+    last_update_id.lock().unwrap().clear();
+
+    // While the connection is open, forward control messages to the feed and
+    // demultiplex incoming frames to their book. An event should be sent on
+    // the connection at least once a second per subscribed stream; error out
+    // if nothing arrives for 5 seconds so a stale connection gets replaced.
+    loop {
+        tokio::select! {
+            control = control_rx.recv() => match control {
+                Some(message) => write.send(message).await?,
+                None => Err(anyhow!("Control channel closed"))?,
+            },
+            message = timeout(Duration::from_secs(5), read.next()) => match message {
+                Err(_) => Err(anyhow!(
+                    "Websocket stream received no new messages for 5 seconds"
+                ))?,
+                Ok(Some(message)) => {
+                    let data = message?.into_data();
+                    let event = match feed.parse_event(&data) {
+                        Ok(event) => {
+                            debug!("Stream event {:?}", event);
+                            event
+                        }
+                        Err(e) => {
+                            info!("Unable to parse message, ignore. Error: {e}");
+                            continue;
+                        }
+                    };
+                    handle_event(books, feed, last_update_id, event).await;
+                }
+                Ok(None) => break,
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Route a single event to its book, seeding the book from a fresh snapshot
+/// the first time it's seen and re-seeding it whenever updates are missed.
+/// Never fails: a single symbol's bad luck (an unparseable sequence gap, a
+/// failed REST snapshot fetch) shouldn't tear down the shared connection
+/// that every other subscribed symbol is relying on too.
+async fn handle_event<F: ExchangeFeed>(
+    books: &Arc<Mutex<HashMap<String, OrderBook>>>,
+    feed: &F,
+    last_update_id: &Arc<Mutex<HashMap<String, u64>>>,
+    event: NormalizedEvent,
+) {
+    let book = match books.lock().unwrap().get(&event.symbol) {
+        Some(book) => book.clone(),
+        None => return, // No longer tracked, ignore
+    };
+
+    if event.is_snapshot {
+        // Partial-depth frames are an absolute top-N snapshot, not a diff,
+        // and carry no sequence-gap info to validate: just apply it.
+        book.replace_levels(&event.bids, &event.asks);
+        last_update_id
+            .lock()
+            .unwrap()
+            .insert(event.symbol.clone(), event.final_update_id);
+        return;
+    }
+
+    let prev = last_update_id.lock().unwrap().get(&event.symbol).copied();
+    if !feed.validate_sequence(prev, &event) {
+        warn!(
+            "Missed updates for {}, re-seeding from a fresh snapshot",
+            event.symbol
+        );
+        last_update_id.lock().unwrap().remove(&event.symbol);
+        return;
+    }
+
+    if prev.is_none() {
+        let snapshot = match feed.snapshot(&event.symbol).await {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                // Leave last_update_id unset so this is retried on the next
+                // event for this symbol, same as the missed-updates path
+                // above, instead of bubbling the error up and killing the
+                // connection for every other subscribed symbol.
+                warn!("Failed to fetch snapshot for {}: {}", event.symbol, e);
+                return;
+            }
+        };
+        // Replace rather than merge, so levels left over from before a
+        // reconnect or a missed-updates gap don't survive the reseed.
+        book.replace_levels(&snapshot.bids, &snapshot.asks);
+    }
+    book.update_from_event(&event);
+    last_update_id
+        .lock()
+        .unwrap()
+        .insert(event.symbol.clone(), event.final_update_id);
+}
+
+/// Serve a single downstream client: send the requested book's current
+/// snapshot, then forward every subsequent delta until the client
+/// disconnects or its own update channel is closed.
+async fn serve_client(stream: TcpStream, books: Arc<Mutex<HashMap<String, OrderBook>>>) -> Result<()> {
+    let ws_stream = tokio_tungstenite::accept_async(stream).await?;
+    let (mut write, mut read) = ws_stream.split();
+
+    let symbol = match read.next().await {
+        Some(message) => message?.into_text()?.trim().to_lowercase(),
+        None => return Ok(()),
+    };
+    let book = books
+        .lock()
+        .unwrap()
+        .get(&symbol)
+        .cloned()
+        .ok_or_else(|| anyhow!("Unknown symbol {}", symbol))?;
+
+    // Subscribe before taking the snapshot, so any delta applied in between
+    // (including the time spent awaiting the write below) overlaps with the
+    // snapshot as a harmless duplicate apply instead of being silently lost.
+    let mut updates = book.subscribe_updates();
+    write
+        .send(Message::Text(serde_json::to_string(&book.snapshot())?))
+        .await?;
+
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(delta) => write.send(Message::Text(serde_json::to_string(&delta)?)).await?,
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    warn!("Client for {} lagged by {} updates, resyncing with a fresh snapshot", symbol, n);
+                    write.send(Message::Text(serde_json::to_string(&book.snapshot())?)).await?;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = read.next() => match message {
+                Some(Ok(_)) => {} // Ignore further messages from the client
+                Some(Err(e)) => return Err(e.into()),
+                None => break, // Client disconnected
+            },
+        }
+    }
+
+    Ok(())
+}
+
 /// Struct representing an OrderBook for a given security
 /// - BTreeMaps are used to keep price levels ordered
 /// - Arc/Mutexes are used so that the book can be updated
@@ -48,116 +371,129 @@ pub struct OrderBook {
     /// asks - Mapping of price levels to quantities
     /// Raw floats can't be used as keys in maps so use FloatOrd instead
     asks: Arc<Mutex<BTreeMap<FloatOrd<f64>, f64>>>,
+    /// Fires a `BookDelta` every time `update_from_event` applies one, so
+    /// downstream clients (e.g. `OrderBooks::serve`) can follow along.
+    updates: broadcast::Sender<BookDelta>,
+    /// If set, the book is pruned down to this many price levels per side
+    /// after every update, e.g. to match a partial-depth stream.
+    max_levels: Option<usize>,
 }
 
 impl OrderBook {
     pub fn new(name: String) -> Self {
+        Self::with_max_levels(name, None)
+    }
+
+    pub fn with_max_levels(name: String, max_levels: Option<usize>) -> Self {
+        let (updates, _) = broadcast::channel(UPDATE_CHANNEL_CAPACITY);
         Self {
             name,
             bids: Arc::new(Mutex::new(BTreeMap::new())),
             asks: Arc::new(Mutex::new(BTreeMap::new())),
+            updates,
+            max_levels,
         }
     }
-}
 
-impl OrderBook {
-    /// Update the order book forever, graciously handling errors
-    pub async fn update_forever(self) {
-        loop {
-            match self.update_until_error().await {
-                Ok(_) => info!("Stream closed for OrderBook {}", self.name),
-                Err(e) => warn!("OrderBook {} failed with error: {}", self.name, e),
-            }
-        }
+    /// Subscribe to this book's stream of deltas, e.g. to re-broadcast it.
+    pub fn subscribe_updates(&self) -> broadcast::Receiver<BookDelta> {
+        self.updates.subscribe()
     }
 
-    /// Use the binance snapshot API and websockets API to update the order book
-    /// until the websocket connection closes or an error is hit
-    pub async fn update_until_error(&self) -> Result<()> {
-        // Clear any existing state, as it may be invalid
-        self.asks.lock().unwrap().clear();
-        self.bids.lock().unwrap().clear();
-
-        // Initialise websocket stream
-        let (ws_stream, _) = connect_async(self.event_stream_url()).await?;
-        let (_, mut read) = ws_stream.split();
-
-        // Wait until events start arriving on the stream
-        let first_event: StreamEvent = serde_json::from_slice(
-            &read
-                .next()
-                .await
-                .ok_or_else(|| anyhow!("Failed to read stream"))??
-                .into_data(),
-        )?;
-        let mut last_update_id = first_event.final_update_id;
-
-        // Get a snapshot to initially populate the order book
-        let snapshot = reqwest::get(self.snapshot_url())
-            .await?
-            .json::<Snapshot>()
-            .await?;
-        self.populate_from_snapshot(&snapshot);
-
-        // While the websocket connection is open, update the order book with events from the stream.
-        // An event should be sent on the connection at least once a second. To prevent stale
-        // order books, error out if no new message is received in 5 seconds
-        loop {
-            match timeout(Duration::from_secs(5), read.next()).await {
-                Err(_) => Err(anyhow!(
-                    "Websocket stream received no new messages for 5 seconds"
-                ))?,
-                Ok(Some(message)) => {
-                    let data = message?.into_data();
-                    let event: StreamEvent = match serde_json::from_slice(&data) {
-                        Ok(event) => {
-                            debug!("Stream event {:?}", event);
-                            event
-                        }
-                        Err(e) => {
-                            info!("Unable to parse message, ignore. Error: {e}");
-                            continue;
-                        }
-                    };
+    /// A JSON-serializable snapshot of the book's current levels, sorted
+    /// best-first (highest bid first, lowest ask first).
+    pub fn snapshot(&self) -> BookSnapshot {
+        BookSnapshot {
+            symbol: self.name.clone(),
+            bids: self
+                .bids
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .map(|(price, quantity)| (price.0, *quantity))
+                .collect(),
+            asks: self
+                .asks
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(price, quantity)| (price.0, *quantity))
+                .collect(),
+        }
+    }
+}
 
-                    // Verify that no events have been missed
-                    if event.first_update_id != last_update_id + 1 {
-                        Err(anyhow!("Missed updates"))?
-                    } else {
-                        last_update_id = event.final_update_id;
-                    };
+impl OrderBook {
+    pub fn update_from_event(&self, event: &NormalizedEvent) {
+        for &(price, quantity) in event.bids.iter() {
+            self.update_bid(price, quantity);
+        }
 
-                    // Update the order book
-                    self.update_from_event(&event);
-                }
-                Ok(None) => break,
-            }
+        for &(price, quantity) in event.asks.iter() {
+            self.update_ask(price, quantity)
         }
+        self.prune();
 
-        Ok(())
+        // No receivers is the common case (nobody's re-broadcasting this
+        // book), so ignore the error rather than treating it as fatal.
+        let _ = self.updates.send(BookDelta {
+            symbol: self.name.clone(),
+            bids: event.bids.clone(),
+            asks: event.asks.clone(),
+            is_snapshot: false,
+        });
     }
 
-    pub fn update_from_event(&self, event: &StreamEvent) {
-        // Update the bids
-        for bid in event.bids.iter() {
-            self.update_bid(bid.price, bid.quantity);
+    pub fn populate_from_snapshot(&self, snapshot: &NormalizedSnapshot) {
+        for &(price, quantity) in snapshot.bids.iter() {
+            self.update_bid(price, quantity);
         }
 
-        // Update the asks
-        for ask in event.asks.iter() {
-            self.update_ask(ask.price, ask.quantity)
+        for &(price, quantity) in snapshot.asks.iter() {
+            self.update_ask(price, quantity)
         }
+        self.prune();
     }
 
-    pub fn populate_from_snapshot(&self, snapshot: &Snapshot) {
-        // Update the bids
-        for bid in snapshot.bids.iter() {
-            self.update_bid(bid.price, bid.quantity);
+    /// Replace every level on both sides with `bids`/`asks`, e.g. to apply
+    /// a partial-depth stream's absolute top-N snapshot. Unlike
+    /// `update_from_event`, this doesn't merge prices in: a level that's no
+    /// longer present simply isn't carried over, rather than lingering
+    /// until `prune` happens to evict it by distance.
+    pub fn replace_levels(&self, bids: &[(f64, f64)], asks: &[(f64, f64)]) {
+        *self.bids.lock().unwrap() = bids.iter().map(|&(price, qty)| (FloatOrd(price), qty)).collect();
+        *self.asks.lock().unwrap() = asks.iter().map(|&(price, qty)| (FloatOrd(price), qty)).collect();
+        self.prune();
+
+        // No receivers is the common case (nobody's re-broadcasting this
+        // book), so ignore the error rather than treating it as fatal.
+        let _ = self.updates.send(BookDelta {
+            symbol: self.name.clone(),
+            bids: bids.to_vec(),
+            asks: asks.to_vec(),
+            is_snapshot: true,
+        });
+    }
+
+    /// Trim each side down to `max_levels` price levels, dropping the
+    /// worst (furthest from mid) levels first.
+    fn prune(&self) {
+        let Some(max_levels) = self.max_levels else {
+            return;
+        };
+
+        let mut bids = self.bids.lock().unwrap();
+        while bids.len() > max_levels {
+            let worst = *bids.keys().next().expect("bids is non-empty");
+            bids.remove(&worst);
         }
+        drop(bids);
 
-        // Update the asks
-        for ask in snapshot.asks.iter() {
-            self.update_ask(ask.price, ask.quantity)
+        let mut asks = self.asks.lock().unwrap();
+        while asks.len() > max_levels {
+            let worst = *asks.keys().next_back().expect("asks is non-empty");
+            asks.remove(&worst);
         }
     }
 
@@ -177,18 +513,127 @@ impl OrderBook {
         }
     }
 
-    pub fn event_stream_url(&self) -> String {
-        format!(
-            "wss://stream.binance.com:9443/ws/{}@depth",
-            self.name.to_lowercase()
-        )
+    /// The highest price level on the bid side, and its quantity.
+    pub fn best_bid(&self) -> Option<(f64, f64)> {
+        self.bids
+            .lock()
+            .unwrap()
+            .iter()
+            .next_back()
+            .map(|(price, quantity)| (price.0, *quantity))
+    }
+
+    /// The lowest price level on the ask side, and its quantity.
+    pub fn best_ask(&self) -> Option<(f64, f64)> {
+        self.asks
+            .lock()
+            .unwrap()
+            .iter()
+            .next()
+            .map(|(price, quantity)| (price.0, *quantity))
+    }
+
+    /// The gap between the best ask and the best bid.
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask()?.0 - self.best_bid()?.0)
+    }
+
+    /// The midpoint between the best ask and the best bid.
+    pub fn mid_price(&self) -> Option<f64> {
+        Some((self.best_ask()?.0 + self.best_bid()?.0) / 2.0)
     }
 
-    pub fn snapshot_url(&self) -> String {
-        format!("https://api.binance.com/api/v3/depth?symbol={}", self.name)
+    /// Simulate filling a market order of `quantity` against `side`,
+    /// returning the volume-weighted average price to execute it, the
+    /// worst price level touched, and whether the book had enough depth to
+    /// fill the full quantity. Returns `None` if `side` is empty or
+    /// `quantity` isn't positive.
+    pub fn fill_cost(&self, side: Side, quantity: f64) -> Option<Fill> {
+        if quantity <= 0.0 {
+            return None;
+        }
+
+        let levels: Vec<(f64, f64)> = match side {
+            Side::Ask => self
+                .asks
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(price, quantity)| (price.0, *quantity))
+                .collect(),
+            Side::Bid => self
+                .bids
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .map(|(price, quantity)| (price.0, *quantity))
+                .collect(),
+        };
+
+        let mut remaining = quantity;
+        let mut filled = 0.0;
+        let mut notional = 0.0;
+        let mut worst_price = None;
+        for (price, level_qty) in levels {
+            if remaining <= 0.0 {
+                break;
+            }
+            filled += level_qty;
+            notional += price * level_qty.min(remaining);
+            worst_price = Some(price);
+            remaining -= level_qty;
+        }
+
+        Some(Fill {
+            avg_price: notional / quantity,
+            worst_price: worst_price?,
+            filled_fully: filled >= quantity,
+        })
     }
 }
 
+/// Which side of an `OrderBook` to execute a market order against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+/// Result of simulating a market order fill via `OrderBook::fill_cost`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fill {
+    /// Volume-weighted average price across the requested quantity.
+    pub avg_price: f64,
+    /// The worst (furthest from mid) price level touched.
+    pub worst_price: f64,
+    /// False if the book didn't have enough depth to fill the full quantity.
+    pub filled_fully: bool,
+}
+
+/// A point-in-time snapshot of an `OrderBook`'s levels, sent to a client
+/// when it first connects to `OrderBooks::serve`.
+#[derive(Clone, Debug, Serialize)]
+pub struct BookSnapshot {
+    pub symbol: String,
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+/// An update to an `OrderBook`, broadcast after `update_from_event` or
+/// `replace_levels` applies it.
+#[derive(Clone, Debug, Serialize)]
+pub struct BookDelta {
+    pub symbol: String,
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+    /// If true, `bids`/`asks` are an absolute top-N snapshot (from
+    /// `replace_levels`) and a receiver should replace its own levels with
+    /// them outright; if false, they're a diff (from `update_from_event`)
+    /// to merge in as usual.
+    pub is_snapshot: bool,
+}
+
 /// Basic implementation of display displaying up to the top
 /// 20 bids and asks.
 impl fmt::Display for OrderBook {
@@ -226,3 +671,46 @@ impl fmt::Display for OrderBook {
         write!(f, "{}", output_string)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_cost_exact_fill() {
+        let book = OrderBook::new("btcusdt".to_string());
+        book.update_ask(100.0, 5.0);
+        book.update_ask(101.0, 3.0);
+
+        let fill = book.fill_cost(Side::Ask, 8.0).unwrap();
+        assert_eq!(fill.avg_price, (100.0 * 5.0 + 101.0 * 3.0) / 8.0);
+        assert_eq!(fill.worst_price, 101.0);
+        assert!(fill.filled_fully);
+    }
+
+    #[test]
+    fn fill_cost_partial_fill() {
+        let book = OrderBook::new("btcusdt".to_string());
+        book.update_ask(100.0, 5.0);
+
+        let fill = book.fill_cost(Side::Ask, 10.0).unwrap();
+        assert_eq!(fill.worst_price, 100.0);
+        assert!(!fill.filled_fully);
+    }
+
+    #[test]
+    fn fill_cost_empty_side_is_none() {
+        let book = OrderBook::new("btcusdt".to_string());
+        book.update_bid(100.0, 5.0);
+
+        assert!(book.fill_cost(Side::Ask, 1.0).is_none());
+    }
+
+    #[test]
+    fn fill_cost_non_positive_quantity_is_none() {
+        let book = OrderBook::new("btcusdt".to_string());
+        book.update_ask(100.0, 5.0);
+
+        assert!(book.fill_cost(Side::Ask, 0.0).is_none());
+    }
+}
@@ -42,8 +42,7 @@ async fn main() -> Result<()> {
         print!(
             "{}",
             order_books
-                .books
-                .get(&crypto_pairs[pair_index as usize].to_string())
+                .get(&crypto_pairs[pair_index as usize])
                 .unwrap()
         );
     }